@@ -1,72 +1,265 @@
 use crate::errors::AccountError;
+use crate::journal::{self, Record};
 use crate::tx;
 use std::collections::HashMap;
 
+/// The balance of a single account, split into funds that can be spent immediately (`available`,
+/// a.k.a. free), funds held pending dispute resolution (`held`), and funds set aside by
+/// [`Accounts::reserve`] (`reserved`).
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Account {
+    pub available: u64,
+    pub held: u64,
+    pub reserved: u64,
+    pub locked: bool,
+}
+
+/// Which kind of transaction a [`TxRecord`] was created from. Only deposits can be disputed: a
+/// withdrawal's funds have already left the account, so re-holding the same amount would
+/// quarantine currency that isn't there, and a later chargeback would subtract it from
+/// `total_issuance` a second time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TxKind {
+    Deposit,
+    Withdraw,
+}
+
+/// A record of a previously applied deposit or withdrawal, kept so that a later dispute,
+/// resolve, or chargeback can look it up by `tx_id`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct TxRecord {
+    client: String,
+    amount: u64,
+    kind: TxKind,
+    disputed: bool,
+    ever_disputed: bool,
+}
+
+/// An overlay-style freeze on part of an account's balance, set by [`Accounts::set_lock`] and
+/// keyed by a caller-chosen `id` so unrelated subsystems (e.g. staking, vesting) can each
+/// maintain their own lock on the same account without clobbering one another.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Lock {
+    id: String,
+    amount: u64,
+    until: u64,
+}
+
 /// A type for managing accounts and their current currency balance
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct Accounts {
-    pub accounts: HashMap<String, u64>, // id to amount
+    pub accounts: HashMap<String, Account>, // id to balances
+    /// The sum of every account's `available` + `held` + `reserved` balance, saturating at
+    /// `u64::MAX` rather than overflowing
+    pub total_issuance: u64,
+    /// The minimum total balance an account may hold; operations that would leave an account
+    /// with a non-zero balance below this threshold forfeit the dust instead
+    existential_deposit: u64,
+    tx_history: HashMap<u32, TxRecord>,
+    locks: HashMap<String, Vec<Lock>>,
 }
 
 impl Accounts {
-    /// Returns an empty instance of the [`Accounts`] type
-    pub fn new() -> Self {
+    /// Returns an empty instance of the [`Accounts`] type with the given existential deposit.
+    /// Pass `0` to recover the old behavior of never reaping dust accounts.
+    pub fn new(existential_deposit: u64) -> Self {
         Accounts {
             accounts: Default::default(),
+            total_issuance: 0,
+            existential_deposit,
+            tx_history: Default::default(),
+            locks: Default::default(),
         }
     }
 
-    /// Either deposits the `amount` provided into the `signer` account or adds the amount to the existing account.
-    /// # Errors
-    /// Attempted overflow
-    pub fn deposit(&mut self, signer: &str, amount: u64) -> Result<tx::Tx, AccountError> {
-        if let Some(account) = self.accounts.get_mut(signer) {
-            (*account)
-                .checked_add(amount)
-                .map(|r| {
-                    *account = r;
-                    Some(r)
-                })
-                .ok_or(AccountError::OverFunded(
-                    signer.to_string(),
+    /// Rebuilds a ledger from empty (with the given existential deposit) by re-applying every
+    /// record in the journal at `path`, in order. Because `send`'s withdraw/deposit pair was
+    /// journaled as a single atomic record, replay can never apply half a transfer. Dispute,
+    /// resolve, and chargeback calls are journaled too, so a replayed ledger ends up with the
+    /// same held/locked state as the live one, not just the same balances.
+    pub fn replay(path: &str, existential_deposit: u64) -> std::io::Result<Self> {
+        let mut ledger = Self::new(existential_deposit);
+        for record in journal::read_records(path)? {
+            match record {
+                Record::Single(tx::Tx::Deposit {
+                    account,
+                    tx_id,
                     amount,
-                ))
-                // Using map() here is an easy way to only manipulate the non-error result
-                .map(|_| tx::Tx::Deposit {
-                    account: signer.to_string(),
+                }) => {
+                    let _ = ledger.deposit(&account, tx_id, amount);
+                }
+                Record::Single(tx::Tx::Withdraw {
+                    account,
+                    tx_id,
                     amount,
-                })
-        } else {
-            self.accounts.insert(signer.to_string(), amount);
-            Ok(tx::Tx::Deposit {
-                account: signer.to_string(),
+                }) => {
+                    let _ = ledger.withdraw(&account, tx_id, amount);
+                }
+                Record::Pair(
+                    tx::Tx::Withdraw {
+                        account: sender,
+                        tx_id: withdraw_tx_id,
+                        amount,
+                    },
+                    tx::Tx::Deposit {
+                        account: recipient,
+                        tx_id: deposit_tx_id,
+                        ..
+                    },
+                ) => {
+                    let _ = ledger.send(&sender, &recipient, withdraw_tx_id, deposit_tx_id, amount);
+                }
+                Record::Pair(_, _) => {}
+                Record::Dispute { client, tx_id } => ledger.dispute(&client, tx_id),
+                Record::Resolve { client, tx_id } => ledger.resolve(&client, tx_id),
+                Record::Chargeback { client, tx_id } => ledger.chargeback(&client, tx_id),
+            }
+        }
+        Ok(ledger)
+    }
+
+    /// The `tx_id` one past the highest one currently recorded, i.e. the next id a caller can
+    /// safely hand to `deposit` or `withdraw` without clobbering an existing [`TxRecord`].
+    /// Callers that restore a ledger via [`Accounts::replay`] should reseed their own counter
+    /// from this rather than restarting at a fixed value.
+    pub fn next_tx_id(&self) -> u32 {
+        self.tx_history.keys().max().map_or(1, |max| max + 1)
+    }
+
+    fn total_balance(account: &Account) -> u64 {
+        account
+            .available
+            .saturating_add(account.held)
+            .saturating_add(account.reserved)
+    }
+
+    /// Removes `signer`'s account and forfeits its dust if the operation just applied left it
+    /// with a non-zero total balance below the existential deposit.
+    fn reap_dust(&mut self, signer: &str) {
+        let Some(account) = self.accounts.get(signer) else {
+            return;
+        };
+        let total = Self::total_balance(account);
+        if total > 0 && total < self.existential_deposit {
+            self.accounts.remove(signer);
+            self.total_issuance = self.total_issuance.saturating_sub(total);
+        }
+    }
+
+    /// The total amount currently frozen on `signer`'s account: the maximum lock amount among
+    /// active locks sharing an `id`, summed across distinct ids.
+    fn frozen_amount(locks: &HashMap<String, Vec<Lock>>, signer: &str) -> u64 {
+        let Some(locks) = locks.get(signer) else {
+            return 0;
+        };
+        let mut max_by_id: HashMap<&str, u64> = HashMap::new();
+        for lock in locks {
+            let amount = max_by_id.entry(lock.id.as_str()).or_insert(0);
+            *amount = (*amount).max(lock.amount);
+        }
+        max_by_id.values().sum()
+    }
+
+    /// Freezes `amount` of `signer`'s balance under `id` until the ledger's height counter
+    /// reaches `until`. Calling this again with the same `id` overlays rather than replaces the
+    /// existing lock: the effective frozen amount for that `id` is the maximum across all active
+    /// locks sharing it, and locks under different ids stack on top of one another.
+    pub fn set_lock(&mut self, id: &str, signer: &str, amount: u64, until: u64) {
+        self.locks.entry(signer.to_string()).or_default().push(Lock {
+            id: id.to_string(),
+            amount,
+            until,
+        });
+    }
+
+    /// Removes every lock under `id` on `signer`'s account.
+    pub fn remove_lock(&mut self, id: &str, signer: &str) {
+        if let Some(locks) = self.locks.get_mut(signer) {
+            locks.retain(|lock| lock.id != id);
+        }
+    }
+
+    /// Advances the ledger's logical block height, expiring any lock whose `until` is at or
+    /// below `height`.
+    pub fn advance_to(&mut self, height: u64) {
+        for locks in self.locks.values_mut() {
+            locks.retain(|lock| lock.until > height);
+        }
+    }
+
+    /// Either deposits the `amount` provided into the `signer` account or adds the amount to the existing account.
+    /// # Errors
+    /// Attempted overflow, the account is locked, or `signer` is a brand-new account and
+    /// `amount` would leave it below the existential deposit
+    pub fn deposit(&mut self, signer: &str, tx_id: u32, amount: u64) -> Result<tx::Tx, AccountError> {
+        if !self.accounts.contains_key(signer) && amount > 0 && amount < self.existential_deposit {
+            return Err(AccountError::BelowExistentialDeposit(
+                signer.to_string(),
                 amount,
-            })
+            ));
         }
+        let account = self.accounts.entry(signer.to_string()).or_default();
+        if account.locked {
+            return Err(AccountError::Locked(signer.to_string()));
+        }
+        account.available = account
+            .available
+            .checked_add(amount)
+            .ok_or_else(|| AccountError::OverFunded(signer.to_string(), amount))?;
+        self.total_issuance = self.total_issuance.saturating_add(amount);
+        self.tx_history.insert(
+            tx_id,
+            TxRecord {
+                client: signer.to_string(),
+                amount,
+                kind: TxKind::Deposit,
+                disputed: false,
+                ever_disputed: false,
+            },
+        );
+        Ok(tx::Tx::Deposit {
+            account: signer.to_string(),
+            tx_id,
+            amount,
+        })
     }
 
     /// Withdraws the `amount` from the `signer` account.
     /// # Errors
-    /// Attempted overflow
-    pub fn withdraw(&mut self, signer: &str, amount: u64) -> Result<tx::Tx, AccountError> {
-        if let Some(account) = self.accounts.get_mut(signer) {
-            account
-                .checked_sub(amount)
-                .map(|r| {
-                    *account = r;
-                    Some(r)
-                })
-                .ok_or(AccountError::UnderFunded(
-                    signer.to_string(),
-                    amount,
-                ))
-                .map(|_| tx::Tx::Withdraw {
-                    account: signer.to_string(),
-                    amount,
-                })
-        } else {
-            Err(AccountError::NotFound(signer.to_string()))
+    /// Attempted underflow, account not found, the account is locked, or the withdrawal would
+    /// reduce the balance below the account's currently frozen total
+    pub fn withdraw(&mut self, signer: &str, tx_id: u32, amount: u64) -> Result<tx::Tx, AccountError> {
+        let frozen = Self::frozen_amount(&self.locks, signer);
+        let Some(account) = self.accounts.get_mut(signer) else {
+            return Err(AccountError::NotFound(signer.to_string()));
+        };
+        if account.locked {
+            return Err(AccountError::Locked(signer.to_string()));
         }
+        let Some(remaining) = account.available.checked_sub(amount) else {
+            return Err(AccountError::UnderFunded(signer.to_string(), amount));
+        };
+        if remaining < frozen {
+            return Err(AccountError::Frozen(signer.to_string(), frozen));
+        }
+        account.available = remaining;
+        self.total_issuance = self.total_issuance.saturating_sub(amount);
+        self.tx_history.insert(
+            tx_id,
+            TxRecord {
+                client: signer.to_string(),
+                amount,
+                kind: TxKind::Withdraw,
+                disputed: false,
+                ever_disputed: false,
+            },
+        );
+        self.reap_dust(signer);
+        Ok(tx::Tx::Withdraw {
+            account: signer.to_string(),
+            tx_id,
+            amount,
+        })
     }
 
     /// Withdraws the amount from the sender account and deposits it in the recipient account.
@@ -77,6 +270,8 @@ impl Accounts {
         &mut self,
         sender: &str,
         recipient: &str,
+        withdraw_tx_id: u32,
+        deposit_tx_id: u32,
         amount: u64,
     ) -> Result<(tx::Tx, tx::Tx), AccountError> {
         let Some(_) = self.accounts.get_mut(sender) else {
@@ -85,15 +280,10 @@ impl Accounts {
         let Some(_) = self.accounts.get_mut(recipient) else {
             return Err(AccountError::NotFound(recipient.to_string()));
         };
-        let Ok(withdraw) = self.withdraw(sender, amount) else {
-            return Err(AccountError::UnderFunded(
-                sender.to_string(),
-                amount,
-            ));
-        };
-        let Ok(deposit) = self.deposit(recipient, amount) else {
+        let withdraw = self.withdraw(sender, withdraw_tx_id, amount)?;
+        let Ok(deposit) = self.deposit(recipient, deposit_tx_id, amount) else {
             // return the amount to sender
-            self.deposit(sender, amount)?;
+            self.deposit(sender, withdraw_tx_id, amount)?;
             return Err(AccountError::OverFunded(
                 recipient.to_string(),
                 amount,
@@ -101,6 +291,210 @@ impl Accounts {
         };
         Ok((withdraw, deposit))
     }
+
+    /// Creates new currency out of thin air, crediting `signer`'s available balance and raising
+    /// `total_issuance`. Unlike `deposit`, this isn't a transaction in the CSV protocol, so it
+    /// produces no [`tx::Tx`] and isn't subject to dispute.
+    /// # Errors
+    /// Attempted overflow, the account is locked, or `signer` is a brand-new account and
+    /// `amount` would leave it below the existential deposit
+    pub fn mint(&mut self, signer: &str, amount: u64) -> Result<(), AccountError> {
+        if !self.accounts.contains_key(signer) && amount > 0 && amount < self.existential_deposit {
+            return Err(AccountError::BelowExistentialDeposit(
+                signer.to_string(),
+                amount,
+            ));
+        }
+        let account = self.accounts.entry(signer.to_string()).or_default();
+        if account.locked {
+            return Err(AccountError::Locked(signer.to_string()));
+        }
+        account.available = account
+            .available
+            .checked_add(amount)
+            .ok_or_else(|| AccountError::OverFunded(signer.to_string(), amount))?;
+        self.total_issuance = self.total_issuance.saturating_add(amount);
+        Ok(())
+    }
+
+    /// Destroys currency, debiting `signer`'s available balance and lowering `total_issuance`.
+    /// Unlike `withdraw`, this isn't a transaction in the CSV protocol, so it produces no
+    /// [`tx::Tx`].
+    /// # Errors
+    /// Attempted underflow, account not found, or the account is locked
+    pub fn burn(&mut self, signer: &str, amount: u64) -> Result<(), AccountError> {
+        let Some(account) = self.accounts.get_mut(signer) else {
+            return Err(AccountError::NotFound(signer.to_string()));
+        };
+        if account.locked {
+            return Err(AccountError::Locked(signer.to_string()));
+        }
+        account.available = account
+            .available
+            .checked_sub(amount)
+            .ok_or_else(|| AccountError::UnderFunded(signer.to_string(), amount))?;
+        self.total_issuance = self.total_issuance.saturating_sub(amount);
+        self.reap_dust(signer);
+        Ok(())
+    }
+
+    /// Moves `amount` from `signer`'s free (`available`) balance into `reserved`, inspired by
+    /// reservable-currency semantics such as bonding funds for staking.
+    ///
+    /// # Errors
+    /// The account doesn't exist, is locked, or doesn't have enough free balance
+    pub fn reserve(&mut self, signer: &str, amount: u64) -> Result<(), AccountError> {
+        let Some(account) = self.accounts.get_mut(signer) else {
+            return Err(AccountError::NotFound(signer.to_string()));
+        };
+        if account.locked {
+            return Err(AccountError::Locked(signer.to_string()));
+        }
+        let Some(available) = account.available.checked_sub(amount) else {
+            return Err(AccountError::InsufficientFree(signer.to_string(), amount));
+        };
+        let Some(reserved) = account.reserved.checked_add(amount) else {
+            return Err(AccountError::OverFunded(signer.to_string(), amount));
+        };
+        account.available = available;
+        account.reserved = reserved;
+        Ok(())
+    }
+
+    /// Moves up to `amount` from `signer`'s `reserved` balance back into `available`,
+    /// saturating at whatever is currently reserved. Returns the amount actually moved.
+    ///
+    /// # Errors
+    /// The account doesn't exist or is locked
+    pub fn unreserve(&mut self, signer: &str, amount: u64) -> Result<u64, AccountError> {
+        let Some(account) = self.accounts.get_mut(signer) else {
+            return Err(AccountError::NotFound(signer.to_string()));
+        };
+        if account.locked {
+            return Err(AccountError::Locked(signer.to_string()));
+        }
+        let moved = amount.min(account.reserved);
+        account.reserved -= moved;
+        account.available = account
+            .available
+            .checked_add(moved)
+            .ok_or_else(|| AccountError::OverFunded(signer.to_string(), moved))?;
+        Ok(moved)
+    }
+
+    /// Moves `amount` directly out of `from`'s `reserved` balance into `to`'s `available`
+    /// balance.
+    ///
+    /// # Errors
+    /// Either account doesn't exist or is locked, or `from` doesn't have enough reserved balance
+    pub fn repatriate_reserved(
+        &mut self,
+        from: &str,
+        to: &str,
+        amount: u64,
+    ) -> Result<(), AccountError> {
+        match self.accounts.get(to) {
+            Some(account) if account.locked => return Err(AccountError::Locked(to.to_string())),
+            Some(_) => {}
+            None => return Err(AccountError::NotFound(to.to_string())),
+        }
+        let Some(from_account) = self.accounts.get_mut(from) else {
+            return Err(AccountError::NotFound(from.to_string()));
+        };
+        if from_account.locked {
+            return Err(AccountError::Locked(from.to_string()));
+        }
+        let Some(from_reserved) = from_account.reserved.checked_sub(amount) else {
+            return Err(AccountError::InsufficientFree(from.to_string(), amount));
+        };
+        from_account.reserved = from_reserved;
+
+        let to_account = self.accounts.get_mut(to).unwrap();
+        match to_account.available.checked_add(amount) {
+            Some(available) => {
+                to_account.available = available;
+                self.reap_dust(from);
+                Ok(())
+            }
+            None => {
+                // restore the reserved funds we just pulled out of `from`
+                if let Some(from_account) = self.accounts.get_mut(from) {
+                    from_account.reserved = from_account.reserved.saturating_add(amount);
+                }
+                Err(AccountError::OverFunded(to.to_string(), amount))
+            }
+        }
+    }
+
+    /// Moves the amount referenced by `tx_id` from `available` to `held` on `client`'s account,
+    /// freezing it pending investigation.
+    ///
+    /// Unknown tx ids, tx ids belonging to a different client, tx ids that have already been
+    /// disputed, and withdrawals are ignored rather than treated as errors. Only deposits are
+    /// disputable, matching the canonical dispute protocol this is modeled on: a withdrawal's
+    /// funds have already left the account, so there's nothing left to hold.
+    pub fn dispute(&mut self, client: &str, tx_id: u32) {
+        let Some(record) = self.tx_history.get_mut(&tx_id) else {
+            return;
+        };
+        if record.client != client || record.ever_disputed || record.kind != TxKind::Deposit {
+            return;
+        }
+        let Some(account) = self.accounts.get_mut(client) else {
+            return;
+        };
+        let Some(available) = account.available.checked_sub(record.amount) else {
+            return;
+        };
+        account.available = available;
+        account.held = account.held.saturating_add(record.amount);
+        record.disputed = true;
+        record.ever_disputed = true;
+    }
+
+    /// Moves the amount referenced by a disputed `tx_id` back from `held` to `available` on
+    /// `client`'s account.
+    ///
+    /// Unknown tx ids, tx ids belonging to a different client, and tx ids that are not currently
+    /// disputed are ignored.
+    pub fn resolve(&mut self, client: &str, tx_id: u32) {
+        let Some(record) = self.tx_history.get_mut(&tx_id) else {
+            return;
+        };
+        if record.client != client || !record.disputed {
+            return;
+        }
+        let Some(account) = self.accounts.get_mut(client) else {
+            return;
+        };
+        account.held = account.held.saturating_sub(record.amount);
+        account.available = account.available.saturating_add(record.amount);
+        record.disputed = false;
+    }
+
+    /// Removes the amount referenced by a disputed `tx_id` from `held` entirely and locks
+    /// `client`'s account so future deposits and withdrawals are rejected. If the chargeback
+    /// leaves a non-zero total balance below the existential deposit, the account is reaped
+    /// instead, and its locked flag is removed along with it.
+    ///
+    /// Unknown tx ids, tx ids belonging to a different client, and tx ids that are not currently
+    /// disputed are ignored.
+    pub fn chargeback(&mut self, client: &str, tx_id: u32) {
+        let Some(record) = self.tx_history.get_mut(&tx_id) else {
+            return;
+        };
+        if record.client != client || !record.disputed {
+            return;
+        }
+        let Some(account) = self.accounts.get_mut(client) else {
+            return;
+        };
+        account.held = account.held.saturating_sub(record.amount);
+        record.disputed = false;
+        account.locked = true;
+        self.total_issuance = self.total_issuance.saturating_sub(record.amount);
+        self.reap_dust(client);
+    }
 }
 
 #[cfg(test)]
@@ -111,9 +505,9 @@ mod tests {
 
     #[test]
     fn test_accounts_withdraw_underfunded() {
-        let mut accounts = Accounts::new();
-        accounts.deposit("alice", 100).unwrap();
-        let error = accounts.withdraw("alice", 200);
+        let mut accounts = Accounts::new(0);
+        accounts.deposit("alice", 1, 100).unwrap();
+        let error = accounts.withdraw("alice", 2, 200);
         let expected = Err(AccountError::UnderFunded(
             "alice".to_string(),
             200,
@@ -123,9 +517,9 @@ mod tests {
 
     #[test]
     fn test_accounts_deposit_overfunded() {
-        let mut accounts = Accounts::new();
-        accounts.deposit("alice", 100).unwrap();
-        let error = accounts.deposit("alice", u64::MAX);
+        let mut accounts = Accounts::new(0);
+        accounts.deposit("alice", 1, 100).unwrap();
+        let error = accounts.deposit("alice", 2, u64::MAX);
         let expected = Err(AccountError::OverFunded(
             "alice".to_string(),
             u64::MAX,
@@ -135,19 +529,20 @@ mod tests {
 
     #[test]
     fn test_accounts_not_found() {
-        let mut accounts = Accounts::new();
-        let error = accounts.withdraw("alice", u64::MAX);
+        let mut accounts = Accounts::new(0);
+        let error = accounts.withdraw("alice", 1, u64::MAX);
         let expected = Err(AccountError::NotFound("alice".to_string()));
         assert_eq!(error, expected);
     }
 
     #[test]
     fn test_accounts_deposit_success() {
-        let mut accounts = Accounts::new();
-        accounts.deposit("alice", 100).unwrap();
-        let tx = accounts.deposit("alice", 100);
+        let mut accounts = Accounts::new(0);
+        accounts.deposit("alice", 1, 100).unwrap();
+        let tx = accounts.deposit("alice", 2, 100);
         let expected = Ok(tx::Tx::Deposit {
             account: "alice".to_string(),
+            tx_id: 2,
             amount: 100,
         });
         assert_eq!(tx, expected);
@@ -155,11 +550,12 @@ mod tests {
 
     #[test]
     fn test_accounts_withdraw_success() {
-        let mut accounts = Accounts::new();
-        accounts.deposit("alice", u64::MAX).unwrap();
-        let tx = accounts.withdraw("alice", u64::MAX);
+        let mut accounts = Accounts::new(0);
+        accounts.deposit("alice", 1, u64::MAX).unwrap();
+        let tx = accounts.withdraw("alice", 2, u64::MAX);
         let expected = Ok(tx::Tx::Withdraw {
             account: "alice".to_string(),
+            tx_id: 2,
             amount: u64::MAX,
         });
         assert_eq!(tx, expected);
@@ -167,23 +563,23 @@ mod tests {
 
     #[test]
     fn test_send_account_not_found() {
-        let mut accounts = Accounts::new();
-        let got = accounts.send("alice", "bob", u64::MAX);
+        let mut accounts = Accounts::new(0);
+        let got = accounts.send("alice", "bob", 1, 2, u64::MAX);
         let expected = Err(AccountError::NotFound("alice".to_string()));
         assert_eq!(got, expected);
 
-        accounts.deposit("alice", 100).unwrap();
-        let got = accounts.send("alice", "bob", u64::MAX);
+        accounts.deposit("alice", 1, 100).unwrap();
+        let got = accounts.send("alice", "bob", 2, 3, u64::MAX);
         let expected = Err(AccountError::NotFound("bob".to_string()));
         assert_eq!(got, expected);
     }
 
     #[test]
     fn test_send_account_withdraw_error() {
-        let mut accounts = Accounts::new();
-        accounts.deposit("alice", 100).unwrap();
-        accounts.deposit("bob", 100).unwrap();
-        let got = accounts.send("alice", "bob", u64::MAX);
+        let mut accounts = Accounts::new(0);
+        accounts.deposit("alice", 1, 100).unwrap();
+        accounts.deposit("bob", 2, 100).unwrap();
+        let got = accounts.send("alice", "bob", 3, 4, u64::MAX);
         let expected = Err(AccountError::UnderFunded(
             "alice".to_string(),
             u64::MAX,
@@ -193,10 +589,10 @@ mod tests {
 
     #[test]
     fn test_send_account_deposit_error() {
-        let mut accounts = Accounts::new();
-        accounts.deposit("alice", u64::MAX).unwrap();
-        accounts.deposit("bob", 100).unwrap();
-        let got = accounts.send("alice", "bob", u64::MAX);
+        let mut accounts = Accounts::new(0);
+        accounts.deposit("alice", 1, u64::MAX).unwrap();
+        accounts.deposit("bob", 2, 100).unwrap();
+        let got = accounts.send("alice", "bob", 3, 4, u64::MAX);
         let expected = Err(AccountError::OverFunded(
             "bob".to_string(),
             u64::MAX,
@@ -204,27 +600,517 @@ mod tests {
         assert_eq!(got, expected);
 
         // accounts should be untouched
-        let amount = accounts.accounts.get("alice").unwrap();
-        assert_eq!(*amount, u64::MAX);
-        let amount = accounts.accounts.get("bob").unwrap();
-        assert_eq!(*amount, 100);    }
+        let account = accounts.accounts.get("alice").unwrap();
+        assert_eq!(account.available, u64::MAX);
+        let account = accounts.accounts.get("bob").unwrap();
+        assert_eq!(account.available, 100);
+    }
 
     #[test]
     fn test_send_account_success() {
-        let mut accounts = Accounts::new();
-        accounts.deposit("alice", 100).unwrap();
-        accounts.deposit("bob", 100).unwrap();
-        let got = accounts.send("alice", "bob", 100);
+        let mut accounts = Accounts::new(0);
+        accounts.deposit("alice", 1, 100).unwrap();
+        accounts.deposit("bob", 2, 100).unwrap();
+        let got = accounts.send("alice", "bob", 3, 4, 100);
         let expected = Ok((
             tx::Tx::Withdraw {
                 account: "alice".to_string(),
+                tx_id: 3,
                 amount: 100,
             },
             tx::Tx::Deposit {
                 account: "bob".to_string(),
+                tx_id: 4,
                 amount: 100,
             },
         ));
         assert_eq!(got, expected);
     }
+
+    #[test]
+    fn test_dispute_moves_available_to_held() {
+        let mut accounts = Accounts::new(0);
+        accounts.deposit("alice", 1, 100).unwrap();
+        accounts.dispute("alice", 1);
+        let account = accounts.accounts.get("alice").unwrap();
+        assert_eq!(account.available, 0);
+        assert_eq!(account.held, 100);
+    }
+
+    #[test]
+    fn test_dispute_unknown_tx_is_ignored() {
+        let mut accounts = Accounts::new(0);
+        accounts.deposit("alice", 1, 100).unwrap();
+        accounts.dispute("alice", 99);
+        let account = accounts.accounts.get("alice").unwrap();
+        assert_eq!(account.available, 100);
+        assert_eq!(account.held, 0);
+    }
+
+    #[test]
+    fn test_dispute_on_withdrawal_is_ignored() {
+        let mut accounts = Accounts::new(0);
+        accounts.deposit("alice", 1, 100).unwrap();
+        accounts.withdraw("alice", 2, 30).unwrap();
+        accounts.dispute("alice", 2);
+        accounts.chargeback("alice", 2);
+        let account = accounts.accounts.get("alice").unwrap();
+        assert_eq!(account.available, 70);
+        assert_eq!(account.held, 0);
+        assert!(!account.locked);
+        assert_eq!(accounts.total_issuance, 70);
+    }
+
+    #[test]
+    fn test_dispute_mismatched_client_is_ignored() {
+        let mut accounts = Accounts::new(0);
+        accounts.deposit("alice", 1, 100).unwrap();
+        accounts.dispute("bob", 1);
+        let account = accounts.accounts.get("alice").unwrap();
+        assert_eq!(account.available, 100);
+        assert_eq!(account.held, 0);
+    }
+
+    #[test]
+    fn test_resolve_moves_held_back_to_available() {
+        let mut accounts = Accounts::new(0);
+        accounts.deposit("alice", 1, 100).unwrap();
+        accounts.dispute("alice", 1);
+        accounts.resolve("alice", 1);
+        let account = accounts.accounts.get("alice").unwrap();
+        assert_eq!(account.available, 100);
+        assert_eq!(account.held, 0);
+        assert!(!account.locked);
+    }
+
+    #[test]
+    fn test_resolve_without_dispute_is_ignored() {
+        let mut accounts = Accounts::new(0);
+        accounts.deposit("alice", 1, 100).unwrap();
+        accounts.resolve("alice", 1);
+        let account = accounts.accounts.get("alice").unwrap();
+        assert_eq!(account.available, 100);
+        assert_eq!(account.held, 0);
+    }
+
+    #[test]
+    fn test_chargeback_removes_held_and_locks_account() {
+        let mut accounts = Accounts::new(0);
+        accounts.deposit("alice", 1, 100).unwrap();
+        accounts.dispute("alice", 1);
+        accounts.chargeback("alice", 1);
+        let account = accounts.accounts.get("alice").unwrap();
+        assert_eq!(account.available, 0);
+        assert_eq!(account.held, 0);
+        assert!(account.locked);
+    }
+
+    #[test]
+    fn test_locked_account_rejects_deposit_and_withdraw() {
+        let mut accounts = Accounts::new(0);
+        accounts.deposit("alice", 1, 100).unwrap();
+        accounts.dispute("alice", 1);
+        accounts.chargeback("alice", 1);
+
+        let error = accounts.deposit("alice", 2, 50);
+        assert_eq!(error, Err(AccountError::Locked("alice".to_string())));
+
+        let error = accounts.withdraw("alice", 3, 1);
+        assert_eq!(error, Err(AccountError::Locked("alice".to_string())));
+    }
+
+    #[test]
+    fn test_locked_account_rejects_reserve_and_unreserve() {
+        let mut accounts = Accounts::new(0);
+        accounts.deposit("alice", 1, 100).unwrap();
+        accounts.reserve("alice", 40).unwrap();
+        // dispute a second deposit: only 60 of tx 1 is still available, so it can't be held
+        accounts.deposit("alice", 2, 50).unwrap();
+        accounts.dispute("alice", 2);
+        accounts.chargeback("alice", 2);
+
+        let error = accounts.reserve("alice", 10);
+        assert_eq!(error, Err(AccountError::Locked("alice".to_string())));
+
+        let error = accounts.unreserve("alice", 10);
+        assert_eq!(error, Err(AccountError::Locked("alice".to_string())));
+    }
+
+    #[test]
+    fn test_locked_account_rejects_repatriate_reserved_as_source_or_destination() {
+        let mut accounts = Accounts::new(0);
+        accounts.deposit("alice", 1, 100).unwrap();
+        accounts.reserve("alice", 40).unwrap();
+        accounts.deposit("bob", 2, 100).unwrap();
+        // dispute a second deposit: only 60 of tx 1 is still available, so it can't be held
+        accounts.deposit("alice", 3, 50).unwrap();
+        accounts.dispute("alice", 3);
+        accounts.chargeback("alice", 3);
+
+        let error = accounts.repatriate_reserved("alice", "bob", 10);
+        assert_eq!(error, Err(AccountError::Locked("alice".to_string())));
+
+        let error = accounts.repatriate_reserved("bob", "alice", 10);
+        assert_eq!(error, Err(AccountError::Locked("alice".to_string())));
+    }
+
+    #[test]
+    fn test_reserve_moves_available_to_reserved() {
+        let mut accounts = Accounts::new(0);
+        accounts.deposit("alice", 1, 100).unwrap();
+        accounts.reserve("alice", 40).unwrap();
+        let account = accounts.accounts.get("alice").unwrap();
+        assert_eq!(account.available, 60);
+        assert_eq!(account.reserved, 40);
+    }
+
+    #[test]
+    fn test_reserve_insufficient_free() {
+        let mut accounts = Accounts::new(0);
+        accounts.deposit("alice", 1, 100).unwrap();
+        let error = accounts.reserve("alice", 200);
+        assert_eq!(error, Err(AccountError::InsufficientFree("alice".to_string(), 200)));
+    }
+
+    #[test]
+    fn test_unreserve_saturates_at_reserved_amount() {
+        let mut accounts = Accounts::new(0);
+        accounts.deposit("alice", 1, 100).unwrap();
+        accounts.reserve("alice", 40).unwrap();
+        let moved = accounts.unreserve("alice", 1_000).unwrap();
+        assert_eq!(moved, 40);
+        let account = accounts.accounts.get("alice").unwrap();
+        assert_eq!(account.available, 100);
+        assert_eq!(account.reserved, 0);
+    }
+
+    #[test]
+    fn test_repatriate_reserved_moves_into_recipients_free_balance() {
+        let mut accounts = Accounts::new(0);
+        accounts.deposit("alice", 1, 100).unwrap();
+        accounts.deposit("bob", 2, 100).unwrap();
+        accounts.reserve("alice", 40).unwrap();
+        accounts.repatriate_reserved("alice", "bob", 40).unwrap();
+        let alice = accounts.accounts.get("alice").unwrap();
+        assert_eq!(alice.available, 60);
+        assert_eq!(alice.reserved, 0);
+        let bob = accounts.accounts.get("bob").unwrap();
+        assert_eq!(bob.available, 140);
+    }
+
+    #[test]
+    fn test_repatriate_reserved_leaving_dust_reaps_sender() {
+        let mut accounts = Accounts::new(10);
+        accounts.deposit("alice", 1, 100).unwrap();
+        accounts.deposit("bob", 2, 100).unwrap();
+        accounts.reserve("alice", 95).unwrap();
+        accounts.repatriate_reserved("alice", "bob", 95).unwrap();
+        // 5 units of dust left in `available` are below the existential deposit and forfeited
+        assert!(!accounts.accounts.contains_key("alice"));
+        assert_eq!(accounts.total_issuance, 195);
+    }
+
+    #[test]
+    fn test_repatriate_reserved_insufficient_reserved() {
+        let mut accounts = Accounts::new(0);
+        accounts.deposit("alice", 1, 100).unwrap();
+        accounts.deposit("bob", 2, 100).unwrap();
+        let error = accounts.repatriate_reserved("alice", "bob", 40);
+        assert_eq!(error, Err(AccountError::InsufficientFree("alice".to_string(), 40)));
+    }
+
+    #[test]
+    fn test_withdraw_only_draws_from_free_balance() {
+        let mut accounts = Accounts::new(0);
+        accounts.deposit("alice", 1, 100).unwrap();
+        accounts.reserve("alice", 60).unwrap();
+        let error = accounts.withdraw("alice", 2, 50);
+        assert_eq!(error, Err(AccountError::UnderFunded("alice".to_string(), 50)));
+    }
+
+    #[test]
+    fn test_tx_can_only_be_disputed_once() {
+        let mut accounts = Accounts::new(0);
+        accounts.deposit("alice", 1, 100).unwrap();
+        accounts.dispute("alice", 1);
+        accounts.resolve("alice", 1);
+        // already resolved once; a second dispute on the same tx must be ignored
+        accounts.dispute("alice", 1);
+        let account = accounts.accounts.get("alice").unwrap();
+        assert_eq!(account.available, 100);
+        assert_eq!(account.held, 0);
+    }
+
+    #[test]
+    fn test_total_issuance_tracks_deposits_and_withdrawals() {
+        let mut accounts = Accounts::new(0);
+        accounts.deposit("alice", 1, 100).unwrap();
+        assert_eq!(accounts.total_issuance, 100);
+        accounts.withdraw("alice", 2, 40).unwrap();
+        assert_eq!(accounts.total_issuance, 60);
+    }
+
+    #[test]
+    fn test_send_leaves_total_issuance_unchanged() {
+        let mut accounts = Accounts::new(0);
+        accounts.deposit("alice", 1, 100).unwrap();
+        accounts.deposit("bob", 2, 100).unwrap();
+        let before = accounts.total_issuance;
+        accounts.send("alice", "bob", 3, 4, 50).unwrap();
+        assert_eq!(accounts.total_issuance, before);
+    }
+
+    #[test]
+    fn test_mint_and_burn_adjust_total_issuance() {
+        let mut accounts = Accounts::new(0);
+        accounts.mint("alice", 100).unwrap();
+        assert_eq!(accounts.total_issuance, 100);
+        let account = accounts.accounts.get("alice").unwrap();
+        assert_eq!(account.available, 100);
+
+        accounts.burn("alice", 40).unwrap();
+        assert_eq!(accounts.total_issuance, 60);
+        let account = accounts.accounts.get("alice").unwrap();
+        assert_eq!(account.available, 60);
+    }
+
+    #[test]
+    fn test_one_accounts_near_max_balance_does_not_block_anothers_deposit() {
+        // total_issuance saturates rather than failing the whole ledger's deposits once any
+        // single account's balance pushes it near u64::MAX.
+        let mut accounts = Accounts::new(0);
+        accounts.deposit("alice", 1, u64::MAX).unwrap();
+        let tx = accounts.deposit("bob", 2, 100);
+        let expected = Ok(tx::Tx::Deposit {
+            account: "bob".to_string(),
+            tx_id: 2,
+            amount: 100,
+        });
+        assert_eq!(tx, expected);
+        assert_eq!(accounts.total_issuance, u64::MAX);
+    }
+
+    #[test]
+    fn test_deposit_into_new_account_below_existential_deposit_is_rejected() {
+        let mut accounts = Accounts::new(10);
+        let error = accounts.deposit("alice", 1, 5);
+        assert_eq!(
+            error,
+            Err(AccountError::BelowExistentialDeposit("alice".to_string(), 5))
+        );
+        assert!(!accounts.accounts.contains_key("alice"));
+    }
+
+    #[test]
+    fn test_withdraw_leaving_dust_below_existential_deposit_reaps_account() {
+        let mut accounts = Accounts::new(10);
+        accounts.deposit("alice", 1, 100).unwrap();
+        accounts.withdraw("alice", 2, 95).unwrap();
+        assert!(!accounts.accounts.contains_key("alice"));
+        // the forfeited 5 units of dust are removed from circulation entirely
+        assert_eq!(accounts.total_issuance, 0);
+    }
+
+    #[test]
+    fn test_existential_deposit_of_zero_preserves_old_behavior() {
+        let mut accounts = Accounts::new(0);
+        accounts.deposit("alice", 1, 100).unwrap();
+        accounts.withdraw("alice", 2, 95).unwrap();
+        let account = accounts.accounts.get("alice").unwrap();
+        assert_eq!(account.available, 5);
+    }
+
+    #[test]
+    fn test_withdraw_below_frozen_total_is_rejected() {
+        let mut accounts = Accounts::new(0);
+        accounts.deposit("alice", 1, 100).unwrap();
+        accounts.set_lock("staking", "alice", 60, 100);
+        let error = accounts.withdraw("alice", 2, 50);
+        assert_eq!(error, Err(AccountError::Frozen("alice".to_string(), 60)));
+    }
+
+    #[test]
+    fn test_withdraw_above_frozen_total_succeeds() {
+        let mut accounts = Accounts::new(0);
+        accounts.deposit("alice", 1, 100).unwrap();
+        accounts.set_lock("staking", "alice", 60, 100);
+        accounts.withdraw("alice", 2, 30).unwrap();
+        let account = accounts.accounts.get("alice").unwrap();
+        assert_eq!(account.available, 70);
+    }
+
+    #[test]
+    fn test_locks_with_same_id_overlay_to_the_maximum() {
+        let mut accounts = Accounts::new(0);
+        accounts.deposit("alice", 1, 100).unwrap();
+        accounts.set_lock("staking", "alice", 30, 100);
+        accounts.set_lock("staking", "alice", 60, 100);
+        // the smaller earlier lock under the same id doesn't add on top of the later one
+        let error = accounts.withdraw("alice", 2, 45);
+        assert_eq!(error, Err(AccountError::Frozen("alice".to_string(), 60)));
+    }
+
+    #[test]
+    fn test_locks_with_different_ids_stack() {
+        let mut accounts = Accounts::new(0);
+        accounts.deposit("alice", 1, 100).unwrap();
+        accounts.set_lock("staking", "alice", 30, 100);
+        accounts.set_lock("vesting", "alice", 20, 100);
+        let error = accounts.withdraw("alice", 2, 55);
+        assert_eq!(error, Err(AccountError::Frozen("alice".to_string(), 50)));
+        accounts.withdraw("alice", 3, 50).unwrap();
+        let account = accounts.accounts.get("alice").unwrap();
+        assert_eq!(account.available, 50);
+    }
+
+    #[test]
+    fn test_remove_lock_unfreezes_balance() {
+        let mut accounts = Accounts::new(0);
+        accounts.deposit("alice", 1, 100).unwrap();
+        accounts.set_lock("staking", "alice", 60, 100);
+        accounts.remove_lock("staking", "alice");
+        accounts.withdraw("alice", 2, 100).unwrap();
+        let account = accounts.accounts.get("alice").unwrap();
+        assert_eq!(account.available, 0);
+    }
+
+    #[test]
+    fn test_advance_to_expires_locks() {
+        let mut accounts = Accounts::new(0);
+        accounts.deposit("alice", 1, 100).unwrap();
+        accounts.set_lock("staking", "alice", 60, 10);
+        accounts.advance_to(10);
+        accounts.withdraw("alice", 2, 100).unwrap();
+        let account = accounts.accounts.get("alice").unwrap();
+        assert_eq!(account.available, 0);
+    }
+
+    #[test]
+    fn test_send_rejects_when_sender_is_frozen() {
+        let mut accounts = Accounts::new(0);
+        accounts.deposit("alice", 1, 100).unwrap();
+        accounts.deposit("bob", 2, 100).unwrap();
+        accounts.set_lock("staking", "alice", 60, 100);
+        let error = accounts.send("alice", "bob", 3, 4, 50);
+        assert_eq!(error, Err(AccountError::Frozen("alice".to_string(), 60)));
+    }
+
+    #[test]
+    fn test_replay_reproduces_live_balances() {
+        use crate::journal::Journal;
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        static COUNTER: AtomicU32 = AtomicU32::new(0);
+        let path = std::env::temp_dir().join(format!(
+            "accounts-journal-replay-test-{}-{}",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let path = path.to_str().unwrap().to_string();
+        let _ = std::fs::remove_file(&path);
+
+        let mut live = Accounts::new(0);
+        let mut journal = Journal::open(&path).unwrap();
+
+        let tx = live.deposit("alice", 1, 100).unwrap();
+        journal.record(tx).unwrap();
+        let tx = live.deposit("bob", 2, 100).unwrap();
+        journal.record(tx).unwrap();
+        let (withdraw, deposit) = live.send("alice", "bob", 3, 4, 40).unwrap();
+        journal.record_pair(withdraw, deposit).unwrap();
+        let tx = live.withdraw("bob", 5, 25).unwrap();
+        journal.record(tx).unwrap();
+        live.dispute("alice", 1);
+        journal.record_dispute("alice", 1).unwrap();
+        live.chargeback("alice", 1);
+        journal.record_chargeback("alice", 1).unwrap();
+
+        let replayed = Accounts::replay(&path, 0).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(replayed.accounts, live.accounts);
+        assert_eq!(replayed.total_issuance, live.total_issuance);
+    }
+
+    #[test]
+    fn test_replay_reproduces_locked_account_after_chargeback() {
+        use crate::journal::Journal;
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        static COUNTER: AtomicU32 = AtomicU32::new(0);
+        let path = std::env::temp_dir().join(format!(
+            "accounts-journal-chargeback-replay-test-{}-{}",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let path = path.to_str().unwrap().to_string();
+        let _ = std::fs::remove_file(&path);
+
+        let mut live = Accounts::new(0);
+        let mut journal = Journal::open(&path).unwrap();
+
+        let tx = live.deposit("alice", 1, 100).unwrap();
+        journal.record(tx).unwrap();
+        live.dispute("alice", 1);
+        journal.record_dispute("alice", 1).unwrap();
+        live.chargeback("alice", 1);
+        journal.record_chargeback("alice", 1).unwrap();
+
+        let account = live.accounts.get("alice").unwrap();
+        assert!(account.locked);
+        assert_eq!(account.available, 0);
+        assert_eq!(live.total_issuance, 0);
+
+        let replayed = Accounts::replay(&path, 0).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        // a crash must not silently un-freeze a charged-back account or resurrect its funds
+        assert_eq!(replayed.accounts, live.accounts);
+        assert_eq!(replayed.total_issuance, live.total_issuance);
+    }
+
+    #[test]
+    fn test_next_tx_id_is_seeded_past_every_recorded_tx() {
+        let mut accounts = Accounts::new(0);
+        assert_eq!(accounts.next_tx_id(), 1);
+        accounts.deposit("alice", 1, 100).unwrap();
+        accounts.deposit("bob", 2, 100).unwrap();
+        assert_eq!(accounts.next_tx_id(), 3);
+    }
+
+    #[test]
+    fn test_next_tx_id_after_replay_does_not_clobber_existing_tx_history() {
+        use crate::journal::Journal;
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        static COUNTER: AtomicU32 = AtomicU32::new(0);
+        let path = std::env::temp_dir().join(format!(
+            "accounts-journal-next-tx-id-test-{}-{}",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let path = path.to_str().unwrap().to_string();
+        let _ = std::fs::remove_file(&path);
+
+        let mut live = Accounts::new(0);
+        let mut journal = Journal::open(&path).unwrap();
+        let tx = live.deposit("alice", 1, 100).unwrap();
+        journal.record(tx).unwrap();
+        let tx = live.deposit("bob", 2, 100).unwrap();
+        journal.record(tx).unwrap();
+
+        // restart: replaying must seed the next tx_id past 2, not reset it to 1
+        let mut restarted = Accounts::replay(&path, 0).unwrap();
+        let next_tx_id = restarted.next_tx_id();
+        assert_eq!(next_tx_id, 3);
+
+        // a deposit using the correctly seeded id must not clobber alice's original tx_id 1
+        restarted.deposit("bob", next_tx_id, 50).unwrap();
+        restarted.dispute("alice", 1);
+        std::fs::remove_file(&path).unwrap();
+
+        let alice = restarted.accounts.get("alice").unwrap();
+        assert_eq!(alice.available, 0);
+        assert_eq!(alice.held, 100);
+    }
 }
@@ -0,0 +1,131 @@
+use crate::accounts::Accounts;
+
+/// Amounts in the CSV protocol are decimals with up to four digits of fractional precision.
+/// Internally we keep the existing `u64` checked-math invariants by scaling every amount up
+/// into this many ten-thousandths of a unit.
+const AMOUNT_SCALE: u64 = 10_000;
+
+/// Reads a transaction stream in `type,client,tx,amount` CSV format from `path`, applies each
+/// row against a fresh [`Accounts`] ledger with the given `existential_deposit`, and writes a
+/// `client,available,held,total,locked` summary to stdout.
+///
+/// Malformed rows (bad tx ids, unparsable amounts, unknown transaction types) are skipped so a
+/// single bad line doesn't abort the run.
+pub fn run(path: &str, existential_deposit: u64) {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) => {
+            eprintln!("failed to read '{}': {}", path, e);
+            return;
+        }
+    };
+
+    let mut ledger = Accounts::new(existential_deposit);
+    for line in contents.lines().skip(1) {
+        apply_row(&mut ledger, line);
+    }
+
+    print_summary(&ledger);
+}
+
+fn apply_row(ledger: &mut Accounts, line: &str) {
+    let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+    let Some(kind) = fields.first() else {
+        return;
+    };
+    let Some(client) = fields.get(1) else {
+        return;
+    };
+    let Some(Ok(tx_id)) = fields.get(2).map(|tx| tx.parse::<u32>()) else {
+        return;
+    };
+    let amount_field = fields.get(3).copied().unwrap_or("");
+
+    match *kind {
+        "deposit" => {
+            let Some(amount) = parse_amount(amount_field) else {
+                return;
+            };
+            let _ = ledger.deposit(client, tx_id, amount);
+        }
+        "withdrawal" => {
+            let Some(amount) = parse_amount(amount_field) else {
+                return;
+            };
+            let _ = ledger.withdraw(client, tx_id, amount);
+        }
+        "dispute" => ledger.dispute(client, tx_id),
+        "resolve" => ledger.resolve(client, tx_id),
+        "chargeback" => ledger.chargeback(client, tx_id),
+        _ => {}
+    }
+}
+
+/// Parses a decimal string with at most four fractional digits into an integer count of
+/// ten-thousandths, e.g. `"2.742"` becomes `27420`.
+fn parse_amount(raw: &str) -> Option<u64> {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return None;
+    }
+    let mut parts = raw.splitn(2, '.');
+    let whole: u64 = parts.next()?.parse().ok()?;
+    let frac = parts.next().unwrap_or("");
+    if frac.len() > 4 || !frac.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let mut frac_digits = frac.to_string();
+    while frac_digits.len() < 4 {
+        frac_digits.push('0');
+    }
+    let frac: u64 = frac_digits.parse().ok()?;
+    whole.checked_mul(AMOUNT_SCALE)?.checked_add(frac)
+}
+
+fn format_amount(raw: u64) -> String {
+    format!("{}.{:04}", raw / AMOUNT_SCALE, raw % AMOUNT_SCALE)
+}
+
+fn print_summary(ledger: &Accounts) {
+    println!("client,available,held,total,locked");
+    let mut clients: Vec<&String> = ledger.accounts.keys().collect();
+    clients.sort();
+    for client in clients {
+        let account = &ledger.accounts[client];
+        let total = account.available.saturating_add(account.held);
+        println!(
+            "{},{},{},{},{}",
+            client,
+            format_amount(account.available),
+            format_amount(account.held),
+            format_amount(total),
+            account.locked
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_amount;
+
+    #[test]
+    fn test_parse_amount_with_fractional_digits() {
+        assert_eq!(parse_amount("2.742"), Some(27420));
+    }
+
+    #[test]
+    fn test_parse_amount_whole_number() {
+        assert_eq!(parse_amount("5"), Some(50000));
+    }
+
+    #[test]
+    fn test_parse_amount_rejects_too_much_precision() {
+        assert_eq!(parse_amount("1.23456"), None);
+    }
+
+    #[test]
+    fn test_parse_amount_rejects_blank() {
+        assert_eq!(parse_amount(""), None);
+        assert_eq!(parse_amount("   "), None);
+    }
+}
@@ -1,12 +1,28 @@
 /// An application-specific error type
 #[derive(Debug, PartialEq, Eq)]
-pub enum AccountingError {
+pub enum AccountError {
     /// Account wasn't found
-    AccountNotFound(String),
+    NotFound(String),
 
     /// Not enough currency in the account (underflow)
-    AccountUnderFunded(String, u64),
+    UnderFunded(String, u64),
 
     /// Too much currency in the account (overflow)
-    AccountOverFunded(String, u64),
+    OverFunded(String, u64),
+
+    /// The account is locked following a chargeback and can no longer be deposited to or
+    /// withdrawn from
+    Locked(String),
+
+    /// Not enough free (unreserved) currency in the account to cover a reserve, or not enough
+    /// reserved currency to cover a repatriation
+    InsufficientFree(String, u64),
+
+    /// Depositing this amount into a brand-new account would leave it with a balance below the
+    /// ledger's existential deposit, so the account was never created
+    BelowExistentialDeposit(String, u64),
+
+    /// The withdrawal or send would reduce the account's balance below its currently frozen
+    /// total (the u64 is that frozen total)
+    Frozen(String, u64),
 }
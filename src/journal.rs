@@ -0,0 +1,216 @@
+use crate::tx;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+
+/// A single journal entry. `Accounts::send` produces a withdraw/deposit pair that must both
+/// succeed or both fail, so they're grouped into one atomic `Pair` record rather than two lines
+/// a crash could split apart mid-write. The `Dispute`/`Resolve`/`Chargeback` variants record a
+/// call to the matching `Accounts` method rather than a `tx::Tx`, since dispute-lifecycle events
+/// never produce one of their own; replaying them re-invokes the same (possibly no-op) method.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Record {
+    Single(tx::Tx),
+    Pair(tx::Tx, tx::Tx),
+    Dispute { client: String, tx_id: u32 },
+    Resolve { client: String, tx_id: u32 },
+    Chargeback { client: String, tx_id: u32 },
+}
+
+/// An append-only, newline-delimited log of every transaction successfully applied against an
+/// `Accounts` ledger, durable enough to survive a crash and be replayed with
+/// [`crate::accounts::Accounts::replay`].
+pub struct Journal {
+    file: File,
+    next_seq: u64,
+}
+
+impl Journal {
+    /// Opens (creating if necessary) the journal file at `path` for appending, continuing the
+    /// sequence number where a previous run left off.
+    pub fn open(path: &str) -> std::io::Result<Self> {
+        let next_seq = std::fs::read_to_string(path)
+            .map(|contents| contents.lines().count() as u64)
+            .unwrap_or(0)
+            + 1;
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Journal { file, next_seq })
+    }
+
+    /// Appends a single deposit or withdrawal to the journal.
+    pub fn record(&mut self, tx: tx::Tx) -> std::io::Result<()> {
+        self.append(&Record::Single(tx))
+    }
+
+    /// Appends the withdraw/deposit pair produced by a `send` as one atomic record.
+    pub fn record_pair(&mut self, withdraw: tx::Tx, deposit: tx::Tx) -> std::io::Result<()> {
+        self.append(&Record::Pair(withdraw, deposit))
+    }
+
+    /// Appends a call to [`crate::accounts::Accounts::dispute`].
+    pub fn record_dispute(&mut self, client: &str, tx_id: u32) -> std::io::Result<()> {
+        self.append(&Record::Dispute {
+            client: client.to_string(),
+            tx_id,
+        })
+    }
+
+    /// Appends a call to [`crate::accounts::Accounts::resolve`].
+    pub fn record_resolve(&mut self, client: &str, tx_id: u32) -> std::io::Result<()> {
+        self.append(&Record::Resolve {
+            client: client.to_string(),
+            tx_id,
+        })
+    }
+
+    /// Appends a call to [`crate::accounts::Accounts::chargeback`].
+    pub fn record_chargeback(&mut self, client: &str, tx_id: u32) -> std::io::Result<()> {
+        self.append(&Record::Chargeback {
+            client: client.to_string(),
+            tx_id,
+        })
+    }
+
+    fn append(&mut self, record: &Record) -> std::io::Result<()> {
+        writeln!(self.file, "{}", encode(self.next_seq, record))?;
+        self.file.flush()?;
+        self.next_seq += 1;
+        Ok(())
+    }
+}
+
+/// Parses every line of the journal at `path` into a record, skipping any line that doesn't
+/// parse (so a truncated trailing write from a mid-crash doesn't abort recovery).
+pub(crate) fn read_records(path: &str) -> std::io::Result<Vec<Record>> {
+    let contents = std::fs::read_to_string(path)?;
+    Ok(contents.lines().filter_map(decode).collect())
+}
+
+fn encode(seq: u64, record: &Record) -> String {
+    match record {
+        Record::Single(tx::Tx::Deposit {
+            account,
+            tx_id,
+            amount,
+        }) => format!("{}|deposit|{}|{}|{}", seq, account, tx_id, amount),
+        Record::Single(tx::Tx::Withdraw {
+            account,
+            tx_id,
+            amount,
+        }) => format!("{}|withdraw|{}|{}|{}", seq, account, tx_id, amount),
+        Record::Pair(
+            tx::Tx::Withdraw {
+                account: sender,
+                tx_id: withdraw_tx_id,
+                amount,
+            },
+            tx::Tx::Deposit {
+                account: recipient,
+                tx_id: deposit_tx_id,
+                ..
+            },
+        ) => format!(
+            "{}|send|{}|{}|{}|{}|{}",
+            seq, sender, withdraw_tx_id, recipient, deposit_tx_id, amount
+        ),
+        Record::Pair(_, _) => {
+            unreachable!("send always produces a Withdraw followed by a Deposit")
+        }
+        Record::Dispute { client, tx_id } => format!("{}|dispute|{}|{}", seq, client, tx_id),
+        Record::Resolve { client, tx_id } => format!("{}|resolve|{}|{}", seq, client, tx_id),
+        Record::Chargeback { client, tx_id } => {
+            format!("{}|chargeback|{}|{}", seq, client, tx_id)
+        }
+    }
+}
+
+fn decode(line: &str) -> Option<Record> {
+    let fields: Vec<&str> = line.split('|').collect();
+    match fields.as_slice() {
+        [_, "deposit", account, tx_id, amount] => Some(Record::Single(tx::Tx::Deposit {
+            account: account.to_string(),
+            tx_id: tx_id.parse().ok()?,
+            amount: amount.parse().ok()?,
+        })),
+        [_, "withdraw", account, tx_id, amount] => Some(Record::Single(tx::Tx::Withdraw {
+            account: account.to_string(),
+            tx_id: tx_id.parse().ok()?,
+            amount: amount.parse().ok()?,
+        })),
+        [_, "send", sender, withdraw_tx_id, recipient, deposit_tx_id, amount] => {
+            Some(Record::Pair(
+                tx::Tx::Withdraw {
+                    account: sender.to_string(),
+                    tx_id: withdraw_tx_id.parse().ok()?,
+                    amount: amount.parse().ok()?,
+                },
+                tx::Tx::Deposit {
+                    account: recipient.to_string(),
+                    tx_id: deposit_tx_id.parse().ok()?,
+                    amount: amount.parse().ok()?,
+                },
+            ))
+        }
+        [_, "dispute", client, tx_id] => Some(Record::Dispute {
+            client: client.to_string(),
+            tx_id: tx_id.parse().ok()?,
+        }),
+        [_, "resolve", client, tx_id] => Some(Record::Resolve {
+            client: client.to_string(),
+            tx_id: tx_id.parse().ok()?,
+        }),
+        [_, "chargeback", client, tx_id] => Some(Record::Chargeback {
+            client: client.to_string(),
+            tx_id: tx_id.parse().ok()?,
+        }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_decode_round_trip_for_deposit() {
+        let record = Record::Single(tx::Tx::Deposit {
+            account: "alice".to_string(),
+            tx_id: 1,
+            amount: 100,
+        });
+        let line = encode(1, &record);
+        assert_eq!(decode(&line), Some(record));
+    }
+
+    #[test]
+    fn test_encode_decode_round_trip_for_send() {
+        let record = Record::Pair(
+            tx::Tx::Withdraw {
+                account: "alice".to_string(),
+                tx_id: 1,
+                amount: 50,
+            },
+            tx::Tx::Deposit {
+                account: "bob".to_string(),
+                tx_id: 2,
+                amount: 50,
+            },
+        );
+        let line = encode(3, &record);
+        assert_eq!(decode(&line), Some(record));
+    }
+
+    #[test]
+    fn test_encode_decode_round_trip_for_chargeback() {
+        let record = Record::Chargeback {
+            client: "alice".to_string(),
+            tx_id: 1,
+        };
+        let line = encode(4, &record);
+        assert_eq!(decode(&line), Some(record));
+    }
+
+    #[test]
+    fn test_decode_rejects_malformed_line() {
+        assert_eq!(decode("not a journal line"), None);
+    }
+}
@@ -0,0 +1,179 @@
+use std::collections::HashMap;
+
+/// A minimal JSON value, just expressive enough for the flat request/response bodies the HTTP
+/// API deals in: no nested objects or arrays.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    String(String),
+    Number(u64),
+    Bool(bool),
+}
+
+impl Value {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            Value::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+}
+
+/// Parses a flat JSON object (string, number, and bool fields only) into a field map.
+/// Returns `None` on anything else, including nested objects/arrays and malformed input.
+pub fn parse_object(input: &str) -> Option<HashMap<String, Value>> {
+    let mut chars = input.trim().chars().peekable();
+    expect(&mut chars, '{')?;
+    let mut fields = HashMap::new();
+    skip_whitespace(&mut chars);
+    if chars.peek() == Some(&'}') {
+        chars.next();
+        return chars.next().is_none().then_some(fields);
+    }
+    loop {
+        skip_whitespace(&mut chars);
+        let key = parse_string(&mut chars)?;
+        skip_whitespace(&mut chars);
+        expect(&mut chars, ':')?;
+        skip_whitespace(&mut chars);
+        let value = parse_value(&mut chars)?;
+        fields.insert(key, value);
+        skip_whitespace(&mut chars);
+        match chars.next()? {
+            ',' => continue,
+            '}' => break,
+            _ => return None,
+        }
+    }
+    if chars.next().is_some() {
+        return None;
+    }
+    Some(fields)
+}
+
+/// Renders `fields` as a single-line JSON object, in the given field order.
+pub fn object(fields: &[(&str, Value)]) -> String {
+    let body = fields
+        .iter()
+        .map(|(key, value)| format!("{:?}:{}", key, render_value(value)))
+        .collect::<Vec<_>>()
+        .join(",");
+    format!("{{{}}}", body)
+}
+
+fn render_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => format!("{:?}", s),
+        Value::Number(n) => n.to_string(),
+        Value::Bool(b) => b.to_string(),
+    }
+}
+
+fn parse_value(chars: &mut std::iter::Peekable<std::str::Chars>) -> Option<Value> {
+    match chars.peek()? {
+        '"' => Some(Value::String(parse_string(chars)?)),
+        't' => {
+            take_literal(chars, "true")?;
+            Some(Value::Bool(true))
+        }
+        'f' => {
+            take_literal(chars, "false")?;
+            Some(Value::Bool(false))
+        }
+        _ => parse_number(chars).map(Value::Number),
+    }
+}
+
+fn parse_string(chars: &mut std::iter::Peekable<std::str::Chars>) -> Option<String> {
+    expect(chars, '"')?;
+    let mut out = String::new();
+    loop {
+        match chars.next()? {
+            '"' => return Some(out),
+            '\\' => out.push(chars.next()?),
+            c => out.push(c),
+        }
+    }
+}
+
+fn parse_number(chars: &mut std::iter::Peekable<std::str::Chars>) -> Option<u64> {
+    let mut digits = String::new();
+    while let Some(c) = chars.peek() {
+        if c.is_ascii_digit() {
+            digits.push(*c);
+            chars.next();
+        } else {
+            break;
+        }
+    }
+    if digits.is_empty() {
+        return None;
+    }
+    digits.parse().ok()
+}
+
+fn take_literal(chars: &mut std::iter::Peekable<std::str::Chars>, literal: &str) -> Option<()> {
+    for expected in literal.chars() {
+        if chars.next()? != expected {
+            return None;
+        }
+    }
+    Some(())
+}
+
+fn expect(chars: &mut std::iter::Peekable<std::str::Chars>, expected: char) -> Option<()> {
+    if chars.next()? == expected {
+        Some(())
+    } else {
+        None
+    }
+}
+
+fn skip_whitespace(chars: &mut std::iter::Peekable<std::str::Chars>) {
+    while let Some(c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_object_with_mixed_fields() {
+        let fields = parse_object(r#"{"client":"alice","tx_id":1,"locked":false}"#).unwrap();
+        assert_eq!(fields.get("client"), Some(&Value::String("alice".to_string())));
+        assert_eq!(fields.get("tx_id"), Some(&Value::Number(1)));
+        assert_eq!(fields.get("locked"), Some(&Value::Bool(false)));
+    }
+
+    #[test]
+    fn test_parse_empty_object() {
+        let fields = parse_object("{}").unwrap();
+        assert!(fields.is_empty());
+    }
+
+    #[test]
+    fn test_parse_object_rejects_malformed_input() {
+        assert_eq!(parse_object("not json"), None);
+    }
+
+    #[test]
+    fn test_object_renders_fields_in_order() {
+        let rendered = object(&[
+            ("available", Value::Number(60)),
+            ("locked", Value::Bool(false)),
+        ]);
+        assert_eq!(rendered, r#"{"available":60,"locked":false}"#);
+    }
+}
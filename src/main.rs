@@ -1,9 +1,16 @@
 mod accounts;
+mod batch;
 mod errors;
+mod journal;
+mod json;
+mod server;
 mod tx;
 
+use journal::Journal;
 use std::io::{stdin, Write};
 
+const JOURNAL_PATH: &str = "accounts.journal";
+
 fn read_from_stdin(label: &str) -> String {
     print!("{}", label);
     std::io::stdout().flush().unwrap_or_default();
@@ -16,21 +23,90 @@ fn read_from_stdin(label: &str) -> String {
     line.trim().to_string()
 }
 
+/// Parses the trailing `existential_deposit` arg that `batch`, `serve`, and `interactive` all
+/// accept, defaulting to `0` (never reap dust) when it's absent or unparsable.
+fn parse_existential_deposit(args: &mut impl Iterator<Item = String>) -> u64 {
+    args.next()
+        .and_then(|raw| raw.parse().ok())
+        .unwrap_or(0)
+}
+
 fn main() {
-    let mut ledger = accounts::Accounts::new();
-    let mut tx_log = vec![];
+    let mut args = std::env::args().skip(1);
+    match args.next().as_deref() {
+        Some("batch") => match args.next() {
+            Some(path) => batch::run(&path, parse_existential_deposit(&mut args)),
+            None => eprintln!("usage: accounts batch <path-to-csv> [existential-deposit]"),
+        },
+        Some("serve") => {
+            let addr = args.next().unwrap_or_else(|| "127.0.0.1:8080".to_string());
+            let existential_deposit = parse_existential_deposit(&mut args);
+            if let Err(e) = server::run(&addr, existential_deposit) {
+                eprintln!("server error: {}", e);
+            }
+        }
+        Some("interactive") => run_interactive(parse_existential_deposit(&mut args)),
+        None => run_interactive(0),
+        Some(other) => eprintln!("unknown subcommand '{}'", other),
+    }
+}
+
+fn run_interactive(existential_deposit: u64) {
+    let mut ledger = accounts::Accounts::replay(JOURNAL_PATH, existential_deposit)
+        .unwrap_or_else(|_| accounts::Accounts::new(existential_deposit));
+    let mut journal = match Journal::open(JOURNAL_PATH) {
+        Ok(journal) => Some(journal),
+        Err(e) => {
+            eprintln!("failed to open journal at '{}': {}", JOURNAL_PATH, e);
+            None
+        }
+    };
+    let mut next_tx_id: u32 = ledger.next_tx_id();
     loop {
         let line = read_from_stdin("cmd: ");
         let cmd: Vec<&str> = line.split(" ").collect();
         match cmd.as_slice() {
             ["deposit", amount, "to", signer] => {
-                cmd_deposit(&mut ledger, &mut tx_log, amount, signer);
+                cmd_deposit(&mut ledger, journal.as_mut(), &mut next_tx_id, amount, signer);
             }
             ["withdraw", amount, "from", signer] => {
-                cmd_withdraw(&mut ledger, &mut tx_log, amount, signer);
+                cmd_withdraw(&mut ledger, journal.as_mut(), &mut next_tx_id, amount, signer);
             }
             ["send", amount, "from", from, "to", to] => {
-                cmd_send(&mut ledger, &mut tx_log, amount, from, to);
+                cmd_send(&mut ledger, journal.as_mut(), &mut next_tx_id, amount, from, to);
+            }
+            ["dispute", tx_id, "for", signer] => {
+                cmd_dispute(&mut ledger, journal.as_mut(), tx_id, signer);
+            }
+            ["resolve", tx_id, "for", signer] => {
+                cmd_resolve(&mut ledger, journal.as_mut(), tx_id, signer);
+            }
+            ["chargeback", tx_id, "for", signer] => {
+                cmd_chargeback(&mut ledger, journal.as_mut(), tx_id, signer);
+            }
+            ["mint", amount, "to", signer] => {
+                cmd_mint(&mut ledger, amount, signer);
+            }
+            ["burn", amount, "from", signer] => {
+                cmd_burn(&mut ledger, amount, signer);
+            }
+            ["reserve", amount, "for", signer] => {
+                cmd_reserve(&mut ledger, amount, signer);
+            }
+            ["unreserve", amount, "for", signer] => {
+                cmd_unreserve(&mut ledger, amount, signer);
+            }
+            ["repatriate", amount, "from", from, "to", to] => {
+                cmd_repatriate(&mut ledger, amount, from, to);
+            }
+            ["lock", amount, "of", signer, "as", id, "until", height] => {
+                cmd_lock(&mut ledger, amount, signer, id, height);
+            }
+            ["unlock", id, "of", signer] => {
+                ledger.remove_lock(id, signer);
+            }
+            ["advance", "to", height] => {
+                cmd_advance(&mut ledger, height);
             }
             ["print"] => {
                 println!("{:?}", ledger)
@@ -45,14 +121,24 @@ fn main() {
 
 fn cmd_send(
     ledger: &mut accounts::Accounts,
-    tx_log: &mut Vec<tx::Tx>,
+    journal: Option<&mut Journal>,
+    next_tx_id: &mut u32,
     amount: &&str,
     from: &&str,
     to: &&str,
 ) {
     if let Ok(amount) = amount.parse::<u64>() {
-        match ledger.send(from, to, amount) {
-            Ok((tx1, tx2)) => tx_log.append(vec![tx1, tx2].as_mut()),
+        let withdraw_tx_id = *next_tx_id;
+        let deposit_tx_id = withdraw_tx_id + 1;
+        match ledger.send(from, to, withdraw_tx_id, deposit_tx_id, amount) {
+            Ok((withdraw, deposit)) => {
+                *next_tx_id = deposit_tx_id + 1;
+                if let Some(journal) = journal {
+                    if let Err(e) = journal.record_pair(withdraw, deposit) {
+                        eprintln!("failed to journal transfer: {}", e);
+                    }
+                }
+            }
             Err(e) => {
                 eprintln!("{:?}", e)
             }
@@ -64,13 +150,22 @@ fn cmd_send(
 
 fn cmd_deposit(
     ledger: &mut accounts::Accounts,
-    tx_log: &mut Vec<tx::Tx>,
+    journal: Option<&mut Journal>,
+    next_tx_id: &mut u32,
     amount: &&str,
     signer: &&str,
 ) {
     if let Ok(amount) = amount.parse::<u64>() {
-        match ledger.deposit(signer, amount) {
-            Ok(tx) => tx_log.push(tx),
+        let tx_id = *next_tx_id;
+        match ledger.deposit(signer, tx_id, amount) {
+            Ok(tx) => {
+                *next_tx_id = tx_id + 1;
+                if let Some(journal) = journal {
+                    if let Err(e) = journal.record(tx) {
+                        eprintln!("failed to journal deposit: {}", e);
+                    }
+                }
+            }
             Err(e) => {
                 eprintln!("{:?}", e)
             }
@@ -82,13 +177,22 @@ fn cmd_deposit(
 
 fn cmd_withdraw(
     ledger: &mut accounts::Accounts,
-    tx_log: &mut Vec<tx::Tx>,
+    journal: Option<&mut Journal>,
+    next_tx_id: &mut u32,
     amount: &&str, // todo get rid of one ref
     signer: &&str,
 ) {
     if let Ok(amount) = amount.parse::<u64>() {
-        match ledger.withdraw(signer, amount) {
-            Ok(tx) => tx_log.push(tx),
+        let tx_id = *next_tx_id;
+        match ledger.withdraw(signer, tx_id, amount) {
+            Ok(tx) => {
+                *next_tx_id = tx_id + 1;
+                if let Some(journal) = journal {
+                    if let Err(e) = journal.record(tx) {
+                        eprintln!("failed to journal withdrawal: {}", e);
+                    }
+                }
+            }
             Err(e) => {
                 eprintln!("{:?}", e)
             }
@@ -97,3 +201,122 @@ fn cmd_withdraw(
         eprintln!("failed to parse '{}'", amount);
     };
 }
+
+fn cmd_dispute(
+    ledger: &mut accounts::Accounts,
+    journal: Option<&mut Journal>,
+    tx_id: &&str,
+    signer: &&str,
+) {
+    if let Ok(tx_id) = tx_id.parse::<u32>() {
+        ledger.dispute(signer, tx_id);
+        if let Some(journal) = journal {
+            if let Err(e) = journal.record_dispute(signer, tx_id) {
+                eprintln!("failed to journal dispute: {}", e);
+            }
+        }
+    } else {
+        eprintln!("failed to parse '{}'", tx_id);
+    };
+}
+
+fn cmd_resolve(
+    ledger: &mut accounts::Accounts,
+    journal: Option<&mut Journal>,
+    tx_id: &&str,
+    signer: &&str,
+) {
+    if let Ok(tx_id) = tx_id.parse::<u32>() {
+        ledger.resolve(signer, tx_id);
+        if let Some(journal) = journal {
+            if let Err(e) = journal.record_resolve(signer, tx_id) {
+                eprintln!("failed to journal resolve: {}", e);
+            }
+        }
+    } else {
+        eprintln!("failed to parse '{}'", tx_id);
+    };
+}
+
+fn cmd_chargeback(
+    ledger: &mut accounts::Accounts,
+    journal: Option<&mut Journal>,
+    tx_id: &&str,
+    signer: &&str,
+) {
+    if let Ok(tx_id) = tx_id.parse::<u32>() {
+        ledger.chargeback(signer, tx_id);
+        if let Some(journal) = journal {
+            if let Err(e) = journal.record_chargeback(signer, tx_id) {
+                eprintln!("failed to journal chargeback: {}", e);
+            }
+        }
+    } else {
+        eprintln!("failed to parse '{}'", tx_id);
+    };
+}
+
+fn cmd_mint(ledger: &mut accounts::Accounts, amount: &&str, signer: &&str) {
+    if let Ok(amount) = amount.parse::<u64>() {
+        if let Err(e) = ledger.mint(signer, amount) {
+            eprintln!("{:?}", e)
+        }
+    } else {
+        eprintln!("failed to parse '{}'", amount);
+    };
+}
+
+fn cmd_burn(ledger: &mut accounts::Accounts, amount: &&str, signer: &&str) {
+    if let Ok(amount) = amount.parse::<u64>() {
+        if let Err(e) = ledger.burn(signer, amount) {
+            eprintln!("{:?}", e)
+        }
+    } else {
+        eprintln!("failed to parse '{}'", amount);
+    };
+}
+
+fn cmd_reserve(ledger: &mut accounts::Accounts, amount: &&str, signer: &&str) {
+    if let Ok(amount) = amount.parse::<u64>() {
+        if let Err(e) = ledger.reserve(signer, amount) {
+            eprintln!("{:?}", e)
+        }
+    } else {
+        eprintln!("failed to parse '{}'", amount);
+    };
+}
+
+fn cmd_unreserve(ledger: &mut accounts::Accounts, amount: &&str, signer: &&str) {
+    if let Ok(amount) = amount.parse::<u64>() {
+        if let Err(e) = ledger.unreserve(signer, amount) {
+            eprintln!("{:?}", e)
+        }
+    } else {
+        eprintln!("failed to parse '{}'", amount);
+    };
+}
+
+fn cmd_repatriate(ledger: &mut accounts::Accounts, amount: &&str, from: &&str, to: &&str) {
+    if let Ok(amount) = amount.parse::<u64>() {
+        if let Err(e) = ledger.repatriate_reserved(from, to, amount) {
+            eprintln!("{:?}", e)
+        }
+    } else {
+        eprintln!("failed to parse '{}'", amount);
+    };
+}
+
+fn cmd_lock(ledger: &mut accounts::Accounts, amount: &&str, signer: &&str, id: &&str, until: &&str) {
+    match (amount.parse::<u64>(), until.parse::<u64>()) {
+        (Ok(amount), Ok(until)) => ledger.set_lock(id, signer, amount, until),
+        _ => eprintln!("failed to parse lock amount '{}' or height '{}'", amount, until),
+    };
+}
+
+fn cmd_advance(ledger: &mut accounts::Accounts, height: &&str) {
+    if let Ok(height) = height.parse::<u64>() {
+        ledger.advance_to(height);
+    } else {
+        eprintln!("failed to parse '{}'", height);
+    };
+}
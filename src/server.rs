@@ -0,0 +1,277 @@
+use crate::accounts::{Account, Accounts};
+use crate::errors::AccountError;
+use crate::json::{self, Value};
+use crate::tx;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+
+/// Starts a blocking HTTP/JSON server in front of a fresh [`Accounts`] ledger with the given
+/// `existential_deposit`, so balances and transactions can be driven by network clients instead
+/// of only stdin.
+///
+/// Each connection is handled on its own thread, so the ledger sits behind a [`Mutex`] to keep
+/// concurrent requests from tearing one another's reads or writes.
+pub fn run(addr: &str, existential_deposit: u64) -> std::io::Result<()> {
+    let ledger = Arc::new(Mutex::new(Accounts::new(existential_deposit)));
+    let listener = TcpListener::bind(addr)?;
+    println!("listening on http://{}", addr);
+    for stream in listener.incoming() {
+        let stream = stream?;
+        let ledger = Arc::clone(&ledger);
+        std::thread::spawn(move || {
+            if let Err(e) = handle_connection(stream, &ledger) {
+                eprintln!("failed to handle request: {}", e);
+            }
+        });
+    }
+    Ok(())
+}
+
+fn handle_connection(mut stream: TcpStream, ledger: &Arc<Mutex<Accounts>>) -> std::io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or("").to_string();
+    let path = parts.next().unwrap_or("").to_string();
+
+    let mut content_length = 0usize;
+    loop {
+        let mut header = String::new();
+        reader.read_line(&mut header)?;
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some(value) = header.to_lowercase().strip_prefix("content-length:") {
+            content_length = value.trim().parse().unwrap_or(0);
+        }
+    }
+
+    let mut body = vec![0u8; content_length];
+    reader.read_exact(&mut body)?;
+    let body = String::from_utf8_lossy(&body).to_string();
+
+    let mut ledger = ledger
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let (status, json_body) = route(&method, &path, &body, &mut ledger);
+    write_response(&mut stream, status, &json_body)
+}
+
+fn route(method: &str, path: &str, body: &str, ledger: &mut Accounts) -> (u16, String) {
+    match (method, path.split('?').next().unwrap_or(path)) {
+        ("POST", "/deposit") => handle_deposit(body, ledger),
+        ("POST", "/withdraw") => handle_withdraw(body, ledger),
+        ("POST", "/send") => handle_send(body, ledger),
+        ("GET", path) => match path.strip_prefix("/accounts/") {
+            Some(id) if !id.is_empty() => handle_get_account(id, ledger),
+            _ => (404, error_json("route not found")),
+        },
+        _ => (404, error_json("route not found")),
+    }
+}
+
+fn handle_deposit(body: &str, ledger: &mut Accounts) -> (u16, String) {
+    let Some((client, tx_id, amount)) = parse_tx_request(body) else {
+        return (400, error_json("expected client, tx_id, and amount fields"));
+    };
+    match ledger.deposit(&client, tx_id, amount) {
+        Ok(tx) => (200, tx_json(&tx)),
+        Err(e) => error_response(&e),
+    }
+}
+
+fn handle_withdraw(body: &str, ledger: &mut Accounts) -> (u16, String) {
+    let Some((client, tx_id, amount)) = parse_tx_request(body) else {
+        return (400, error_json("expected client, tx_id, and amount fields"));
+    };
+    match ledger.withdraw(&client, tx_id, amount) {
+        Ok(tx) => (200, tx_json(&tx)),
+        Err(e) => error_response(&e),
+    }
+}
+
+fn handle_send(body: &str, ledger: &mut Accounts) -> (u16, String) {
+    let Some(fields) = json::parse_object(body) else {
+        return (400, error_json("invalid JSON body"));
+    };
+    let sender = fields.get("sender").and_then(Value::as_str);
+    let recipient = fields.get("recipient").and_then(Value::as_str);
+    let withdraw_tx_id = fields.get("withdraw_tx_id").and_then(Value::as_u64);
+    let deposit_tx_id = fields.get("deposit_tx_id").and_then(Value::as_u64);
+    let amount = fields.get("amount").and_then(Value::as_u64);
+    let (Some(sender), Some(recipient), Some(withdraw_tx_id), Some(deposit_tx_id), Some(amount)) =
+        (sender, recipient, withdraw_tx_id, deposit_tx_id, amount)
+    else {
+        return (
+            400,
+            error_json(
+                "expected sender, recipient, withdraw_tx_id, deposit_tx_id, and amount fields",
+            ),
+        );
+    };
+    match ledger.send(
+        sender,
+        recipient,
+        withdraw_tx_id as u32,
+        deposit_tx_id as u32,
+        amount,
+    ) {
+        Ok((withdraw, deposit)) => (
+            200,
+            format!(
+                "{{\"withdraw\":{},\"deposit\":{}}}",
+                tx_json(&withdraw),
+                tx_json(&deposit)
+            ),
+        ),
+        Err(e) => error_response(&e),
+    }
+}
+
+fn handle_get_account(id: &str, ledger: &Accounts) -> (u16, String) {
+    match ledger.accounts.get(id) {
+        Some(account) => (200, account_json(account)),
+        None => error_response(&AccountError::NotFound(id.to_string())),
+    }
+}
+
+/// Pulls the `client`/`tx_id`/`amount` fields shared by deposit and withdrawal request bodies.
+fn parse_tx_request(body: &str) -> Option<(String, u32, u64)> {
+    let fields = json::parse_object(body)?;
+    let client = fields.get("client")?.as_str()?.to_string();
+    let tx_id = fields.get("tx_id")?.as_u64()? as u32;
+    let amount = fields.get("amount")?.as_u64()?;
+    Some((client, tx_id, amount))
+}
+
+fn tx_json(tx: &tx::Tx) -> String {
+    match tx {
+        tx::Tx::Deposit {
+            account,
+            tx_id,
+            amount,
+        } => json::object(&[
+            ("type", Value::String("deposit".to_string())),
+            ("account", Value::String(account.clone())),
+            ("tx_id", Value::Number(*tx_id as u64)),
+            ("amount", Value::Number(*amount)),
+        ]),
+        tx::Tx::Withdraw {
+            account,
+            tx_id,
+            amount,
+        } => json::object(&[
+            ("type", Value::String("withdraw".to_string())),
+            ("account", Value::String(account.clone())),
+            ("tx_id", Value::Number(*tx_id as u64)),
+            ("amount", Value::Number(*amount)),
+        ]),
+    }
+}
+
+fn account_json(account: &Account) -> String {
+    json::object(&[
+        ("available", Value::Number(account.available)),
+        ("held", Value::Number(account.held)),
+        ("reserved", Value::Number(account.reserved)),
+        ("locked", Value::Bool(account.locked)),
+    ])
+}
+
+fn error_json(message: &str) -> String {
+    json::object(&[("error", Value::String(message.to_string()))])
+}
+
+/// Maps an [`AccountError`] onto an HTTP status code and a JSON error body.
+fn error_response(error: &AccountError) -> (u16, String) {
+    let status = match error {
+        AccountError::NotFound(_) => 404,
+        AccountError::UnderFunded(_, _)
+        | AccountError::OverFunded(_, _)
+        | AccountError::Locked(_)
+        | AccountError::InsufficientFree(_, _)
+        | AccountError::BelowExistentialDeposit(_, _)
+        | AccountError::Frozen(_, _) => 409,
+    };
+    (status, error_json(&format!("{:?}", error)))
+}
+
+fn write_response(stream: &mut TcpStream, status: u16, body: &str) -> std::io::Result<()> {
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        409 => "Conflict",
+        _ => "Internal Server Error",
+    };
+    let response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tx_request_with_valid_body() {
+        let parsed = parse_tx_request(r#"{"client":"alice","tx_id":1,"amount":100}"#);
+        assert_eq!(parsed, Some(("alice".to_string(), 1, 100)));
+    }
+
+    #[test]
+    fn test_parse_tx_request_rejects_missing_field() {
+        assert_eq!(parse_tx_request(r#"{"client":"alice","tx_id":1}"#), None);
+    }
+
+    #[test]
+    fn test_route_deposit_and_get_account() {
+        let mut ledger = Accounts::new(0);
+        let (status, _) = route(
+            "POST",
+            "/deposit",
+            r#"{"client":"alice","tx_id":1,"amount":100}"#,
+            &mut ledger,
+        );
+        assert_eq!(status, 200);
+
+        let (status, body) = route("GET", "/accounts/alice", "", &mut ledger);
+        assert_eq!(status, 200);
+        assert_eq!(body, account_json(ledger.accounts.get("alice").unwrap()));
+    }
+
+    #[test]
+    fn test_route_get_unknown_account_is_not_found() {
+        let mut ledger = Accounts::new(0);
+        let (status, _) = route("GET", "/accounts/alice", "", &mut ledger);
+        assert_eq!(status, 404);
+    }
+
+    #[test]
+    fn test_route_unknown_path_is_not_found() {
+        let mut ledger = Accounts::new(0);
+        let (status, _) = route("GET", "/nope", "", &mut ledger);
+        assert_eq!(status, 404);
+    }
+
+    #[test]
+    fn test_error_response_maps_not_found_to_404() {
+        let (status, _) = error_response(&AccountError::NotFound("alice".to_string()));
+        assert_eq!(status, 404);
+    }
+
+    #[test]
+    fn test_error_response_maps_funding_errors_to_409() {
+        let (status, _) = error_response(&AccountError::UnderFunded("alice".to_string(), 50));
+        assert_eq!(status, 409);
+    }
+}
@@ -0,0 +1,17 @@
+/// A receipt of a ledger operation that was successfully applied.
+///
+/// Each variant carries the `tx_id` the operation was recorded under so that later
+/// dispute-related activity can refer back to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Tx {
+    Deposit {
+        account: String,
+        tx_id: u32,
+        amount: u64,
+    },
+    Withdraw {
+        account: String,
+        tx_id: u32,
+        amount: u64,
+    },
+}